@@ -1,35 +1,124 @@
-// Generational References 
-// Adapted from https://vale.dev/blog/generational-references 
+// Generational References
+// Adapted from https://vale.dev/blog/generational-references
 
-use std::{cell::UnsafeCell, marker::PhantomData, ptr::NonNull};
+use std::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
 
 const MAX_ALLOCS: usize = 1 << 9;
 
-struct GrArenaInternal {
+/// An integer type usable as a generation counter.
+///
+/// Narrower types shrink `Weak` (and the arena's `gens` chunks) at the cost
+/// of cycling through the whole counter range sooner: a slot that has been
+/// reused `2^bits - 1` times permanently retires (see `Gr`'s `Drop` impl)
+/// rather than risk a stale `Weak` from the very first cycle wrongly
+/// validating against a value the counter has wrapped back around to.
+/// Retirement leaks at most one slot per `2^bits - 1` reuses, which is the
+/// same safety/footprint tradeoff `generational-arena` documents. `u64` is
+/// the default and is wide enough that this is not a practical concern.
+pub trait GenInt: Copy + Eq + 'static {
+    /// The value a freshly-created slot's generation starts at.
+    const INITIAL: Self;
+    /// A sentinel value that is never assigned as a live generation number,
+    /// reserved to mark a permanently-retired slot.
+    const TOMBSTONE: Self;
+    fn wrapping_next(self) -> Self;
+}
+
+macro_rules! impl_gen_int {
+    ($($t:ty),*) => {
+        $(impl GenInt for $t {
+            const INITIAL: Self = 1;
+            const TOMBSTONE: Self = 0;
+            fn wrapping_next(self) -> Self {
+                self.wrapping_add(1)
+            }
+        })*
+    };
+}
+impl_gen_int!(u16, u32, u64);
+
+/// An integer type usable to index slab slots.
+///
+/// `usize` is the default; a narrower type shrinks `Weak` further for
+/// arenas that will never hold more than e.g. `u32::MAX` live slots. `Gr`
+/// keeps the same size either way, since its two pointers pad it out.
+pub trait IdxInt: Copy + Eq + 'static {
+    /// Converts `v` to `Self`, or `None` if `v` doesn't fit. Callers must
+    /// not fall back to a truncating cast here: a wrapped index would alias
+    /// a slot some other still-live handle already owns, corrupting it.
+    fn from_usize(v: usize) -> Option<Self>;
+    fn as_usize(self) -> usize;
+}
+
+macro_rules! impl_idx_int {
+    ($($t:ty),*) => {
+        $(impl IdxInt for $t {
+            fn from_usize(v: usize) -> Option<Self> {
+                <$t>::try_from(v).ok()
+            }
+            fn as_usize(self) -> usize {
+                self as usize
+            }
+        })*
+    };
+}
+impl_idx_int!(u16, u32, u64, usize);
+
+struct GrArenaInternal<T, G: GenInt = u64, I: IdxInt = usize> {
     // Instead of packing the generational numbers with the allocation,
     // we use an extra memory region. This way we can avoid having to
     // use a custom allocator that guarantees that the generational numbers
     // are never used for anything other than generational numbers.
-    gens: Vec<Box<[u64; MAX_ALLOCS]>>,
+    gens: Vec<Box<[G; MAX_ALLOCS]>>,
+    // Strong reference counts, laid out the same way as `gens` and indexed
+    // by the same `gen_idx`. A slot is only released back to `unused` once
+    // its count drops to zero. Always `u64`: reference counts aren't
+    // subject to the ABA concerns generation numbers are, so there's no
+    // reason to narrow them along with `G`.
+    strong: Vec<Box<[u64; MAX_ALLOCS]>>,
+    // The actual values, laid out the same way as `gens`/`strong` and
+    // indexed by the same `gen_idx`. Values live inline in the slab instead
+    // of behind their own individual allocation, so objects of the same
+    // generation stay contiguous. Chunks are boxed so their addresses stay
+    // stable as new chunks are pushed; a slot only holds an initialized
+    // value while it is handed out (tracked via its nonzero `strong` count).
+    slots: Vec<Box<[MaybeUninit<T>; MAX_ALLOCS]>>,
     // Free list
-    unused: Vec<usize>,
+    unused: Vec<I>,
 }
 
-pub struct GrArena {
-    inner: UnsafeCell<GrArenaInternal>,
+pub struct GrArena<T, G: GenInt = u64, I: IdxInt = usize> {
+    inner: UnsafeCell<GrArenaInternal<T, G, I>>,
 }
 
-impl GrArena {
+/// `GrArena` with the `u64` generations and `usize` indices it used before
+/// gaining the `G`/`I` parameters (chunk0-4). Identical to bare `GrArena<T>`,
+/// which defaults to the same two types -- this alias just lets call sites
+/// name the pre-existing behavior explicitly instead of relying on defaults.
+pub type DefaultGrArena<T> = GrArena<T, u64, usize>;
+
+impl<T, G: GenInt, I: IdxInt> GrArena<T, G, I> {
     pub fn new() -> Self {
         GrArena {
             inner: UnsafeCell::new(GrArenaInternal {
                 gens: Vec::new(),
+                strong: Vec::new(),
+                slots: Vec::new(),
                 unused: Vec::new(),
             })
         }
     }
 
-    pub fn alloc<'a, T>(&'a self, v: T) -> Gr<'a, T> {
+    pub fn alloc<'a>(&'a self, v: T) -> Gr<'a, T, G, I> {
         // Safety:
         // We don't hand out references to the arena.
         // Additionally, nobody else can own the arena mutably, since we borrowed it.
@@ -37,83 +126,256 @@ impl GrArena {
         loop {
             match (*arena).unused.pop() {
                 Some(gen_idx) => {
-                    // Found an unused slot, return a strong reference to it
+                    // Found an unused slot: it starts out with a single owner
+                    let idx = gen_idx.as_usize();
+                    arena.strong[idx / MAX_ALLOCS][idx % MAX_ALLOCS] = 1;
+                    let slot = &mut arena.slots[idx / MAX_ALLOCS][idx % MAX_ALLOCS];
+                    slot.write(v);
                     return Gr {
-                        ptr: NonNull::from(Box::leak(Box::new(v))),
+                        ptr: NonNull::new(slot.as_mut_ptr()).unwrap(),
                         gen_idx: gen_idx,
-                        arena: arena as *mut GrArenaInternal,
+                        arena: arena as *mut GrArenaInternal<T, G, I>,
                         phantom: PhantomData,
                     };
                 }
                 None => {
                     // Add more slots if we ran out
-                    arena.gens.push(Box::new([1; MAX_ALLOCS]));
+                    let base = arena.gens.len() * MAX_ALLOCS;
+                    arena.gens.push(Box::new([G::INITIAL; MAX_ALLOCS]));
+                    arena.strong.push(Box::new([0; MAX_ALLOCS]));
+                    arena.slots.push(Box::new(std::array::from_fn(|_| MaybeUninit::uninit())));
                     for i in 0..MAX_ALLOCS {
-                        arena.unused.push(i + (arena.gens.len()-1) * MAX_ALLOCS);
+                        arena.unused.push(
+                            I::from_usize(i + base)
+                                .expect("GrArena grew past I::MAX live slots"),
+                        );
                     }
                 }
             }
         }
     }
+
+    /// Look up a value by a `Weak` handle, without going through `Weak::get`.
+    ///
+    /// Centralizing the generation check here (instead of only on `Weak`
+    /// itself) lets callers that already hold the arena index straight into
+    /// it, e.g. when sweeping a collection of handles. Reads through
+    /// `weak.gen`/`weak.ptr` directly, like `SyncGrArena::get` does, rather
+    /// than recomputing an index into `self`'s own slabs -- that way a
+    /// `Weak` from some other `GrArena` can't alias into whatever lives at
+    /// the same index here, it just (correctly) fails the generation check
+    /// or points somewhere this arena never reads from its own state.
+    pub fn get(&self, weak: &Weak<'_, T, G, I>) -> Option<&T> {
+        if unsafe { *weak.gen } != weak.alloc_gen {
+            None
+        } else {
+            Some(unsafe { weak.ptr.as_ref() })
+        }
+    }
+
+    /// Like `get`, but for mutable access. Takes `&mut self` since, unlike
+    /// `alloc`, there is no interior-mutability story for handing out `&mut
+    /// T` while other borrows of the arena might be outstanding.
+    pub fn get_mut(&mut self, weak: &Weak<'_, T, G, I>) -> Option<&mut T> {
+        if unsafe { *weak.gen } != weak.alloc_gen {
+            None
+        } else {
+            Some(unsafe { &mut *weak.ptr.as_ptr() })
+        }
+    }
+
+    /// Iterate over every value currently alive in the arena, in slab order.
+    pub fn iter(&self) -> Iter<'_, T, G, I> {
+        let arena = unsafe { &*self.inner.get() };
+        Iter {
+            arena: arena as *const GrArenaInternal<T, G, I>,
+            pos: 0,
+            total: arena.gens.len() * MAX_ALLOCS,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Like `iter`, but yields mutable references.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, G, I> {
+        let arena = unsafe { &mut *self.inner.get() };
+        let total = arena.gens.len() * MAX_ALLOCS;
+        IterMut {
+            arena: arena as *mut GrArenaInternal<T, G, I>,
+            pos: 0,
+            total,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Iterator over live values in a `GrArena`, returned by `GrArena::iter`.
+pub struct Iter<'a, T, G: GenInt = u64, I: IdxInt = usize> {
+    arena: *const GrArenaInternal<T, G, I>,
+    pos: usize,
+    total: usize,
+    // Ties the yielded `&'a T`s to the lifetime of the `&GrArena` this was
+    // created from. Stored as a raw pointer (re-dereferenced per `next`)
+    // rather than a long-lived `&'a GrArenaInternal`, the same way
+    // `IterMut` is: `alloc` only needs `&self` and can run while an `Iter`
+    // is outstanding, so a typed borrow held for the iterator's whole
+    // lifetime would alias a `Vec` buffer `alloc` reallocates mid-sweep.
+    phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T, G: GenInt, I: IdxInt> Iterator for Iter<'a, T, G, I> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while self.pos < self.total {
+            let idx = self.pos;
+            self.pos += 1;
+            unsafe {
+                let arena = &*self.arena;
+                // A slot is live exactly while it has at least one owner.
+                if arena.strong[idx / MAX_ALLOCS][idx % MAX_ALLOCS] > 0 {
+                    let slot = &arena.slots[idx / MAX_ALLOCS][idx % MAX_ALLOCS];
+                    return Some(slot.assume_init_ref());
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over live values in a `GrArena`, returned by `GrArena::iter_mut`.
+pub struct IterMut<'a, T, G: GenInt = u64, I: IdxInt = usize> {
+    arena: *mut GrArenaInternal<T, G, I>,
+    pos: usize,
+    total: usize,
+    // Ties the yielded `&mut T`s to the lifetime of the `&mut GrArena` this
+    // was created from.
+    phantom: PhantomData<&'a mut T>,
 }
 
-pub struct Gr<'a, T> {
+impl<'a, T, G: GenInt, I: IdxInt> Iterator for IterMut<'a, T, G, I> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        while self.pos < self.total {
+            let idx = self.pos;
+            self.pos += 1;
+            unsafe {
+                let arena = &mut *self.arena;
+                // A slot is live exactly while it has at least one owner.
+                if arena.strong[idx / MAX_ALLOCS][idx % MAX_ALLOCS] > 0 {
+                    let slot = &mut arena.slots[idx / MAX_ALLOCS][idx % MAX_ALLOCS];
+                    return Some(slot.assume_init_mut());
+                }
+            }
+        }
+        None
+    }
+}
+
+pub struct Gr<'a, T, G: GenInt = u64, I: IdxInt = usize> {
     // The contained data
     ptr: NonNull<T>,
     // The index into the generational numbers array
-    gen_idx: usize,
+    gen_idx: I,
     // A pointer to the owning arena.
     // Could be removed if we only had a single global arena.
-    arena: *mut GrArenaInternal,
+    arena: *mut GrArenaInternal<T, G, I>,
     // Bind the lifetime of the reference to the lifetime of the generational numbers:
     // Must not outlive the arena
     phantom: std::marker::PhantomData<&'a u64>,
 }
 
-impl<'a, T> Gr<'a, T> {
-    unsafe fn gen(&self) -> *mut u64 {
-        (*self.arena).gens[self.gen_idx / MAX_ALLOCS]
-            .as_mut_ptr()
-            .add(self.gen_idx % MAX_ALLOCS)
+impl<'a, T, G: GenInt, I: IdxInt> Gr<'a, T, G, I> {
+    unsafe fn gen(&self) -> *mut G {
+        let arena = &mut *self.arena;
+        let idx = self.gen_idx.as_usize();
+        arena.gens[idx / MAX_ALLOCS].as_mut_ptr().add(idx % MAX_ALLOCS)
+    }
+
+    unsafe fn strong(&self) -> *mut u64 {
+        let arena = &mut *self.arena;
+        let idx = self.gen_idx.as_usize();
+        arena.strong[idx / MAX_ALLOCS].as_mut_ptr().add(idx % MAX_ALLOCS)
     }
 
-    pub fn weak(&self) -> Weak<'a, T> {
+    pub fn weak(&self) -> Weak<'a, T, G, I> {
         // Get a pointer to the GN
         let gen = unsafe { self.gen() };
         Weak {
             ptr: self.ptr,
             gen: gen,
             alloc_gen: unsafe { *gen },
+            arena: self.arena,
+            gen_idx: self.gen_idx,
             phantom: PhantomData,
         }
     }
 }
 
-impl<'a, T> Drop for Gr<'a, T> {
+impl<'a, T, G: GenInt, I: IdxInt> Clone for Gr<'a, T, G, I> {
+    fn clone(&self) -> Self {
+        // Another owner of the same slot: bump the strong count instead of
+        // allocating anything.
+        unsafe {
+            *self.strong() += 1;
+        }
+        Gr {
+            ptr: self.ptr,
+            gen_idx: self.gen_idx,
+            arena: self.arena,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, G: GenInt, I: IdxInt> Drop for Gr<'a, T, G, I> {
     fn drop(&mut self) {
         unsafe {
-            Box::from_raw(self.ptr.as_mut());
-            let gen = self.gen();
-            *gen += 1;
-            (*self.arena).unused.push(self.gen_idx);
+            let strong = self.strong();
+            *strong -= 1;
+            if *strong == 0 {
+                // We were the last owner: actually free the slot in place,
+                // the backing memory belongs to the arena's slab.
+                std::ptr::drop_in_place(self.ptr.as_ptr());
+                let gen = self.gen();
+                let next = (*gen).wrapping_next();
+                if next == G::TOMBSTONE {
+                    // Bumping the generation would wrap it back around to a
+                    // value some earlier, possibly still-alive `Weak` could
+                    // hold. Retire the slot for good instead of risking
+                    // that ABA collision: write the sentinel (which no real
+                    // allocation ever used) and never hand this slot out
+                    // again.
+                    *gen = G::TOMBSTONE;
+                } else {
+                    *gen = next;
+                    let arena = &mut *self.arena;
+                    arena.unused.push(self.gen_idx);
+                }
+            }
         }
     }
 }
 
-pub struct Weak<'a, T> {
+pub struct Weak<'a, T, G: GenInt = u64, I: IdxInt = usize> {
     // The data
     ptr: NonNull<T>,
     // Unfortunately storing the generational numbers (GNs) separately
     // also means we need to store the location of the GN of interest.
     // Furthermore, we need to dereference 2 pointers to get to the data.
-    gen: *const u64,
+    gen: *const G,
     // The generational number we expect
-    alloc_gen: u64,
+    alloc_gen: G,
+    // A pointer to the owning arena, needed to re-acquire ownership via
+    // `upgrade` (and to find the strong count of this slot).
+    arena: *mut GrArenaInternal<T, G, I>,
+    // The index into the generational numbers array
+    gen_idx: I,
     // Must not outlive the arena
     phantom: std::marker::PhantomData<&'a u64>,
 }
 
-impl<'a, T> Weak<'a, T> {
+impl<'a, T, G: GenInt, I: IdxInt> Weak<'a, T, G, I> {
     pub fn get(&self) -> Option<&T> {
         // Check if the GNs mismatch
         if unsafe { *self.gen } != self.alloc_gen {
@@ -122,15 +384,298 @@ impl<'a, T> Weak<'a, T> {
             unsafe { Some(self.ptr.as_ref()) }
         }
     }
+
+    /// Try to promote this `Weak` back into an owning `Gr`.
+    ///
+    /// Succeeds as long as the slot hasn't been freed since this `Weak`
+    /// was created, i.e. as long as at least one strong owner has kept the
+    /// slot alive in the meantime. On success the strong count is bumped,
+    /// just like `Gr::clone`.
+    pub fn upgrade(&self) -> Option<Gr<'a, T, G, I>> {
+        if unsafe { *self.gen } != self.alloc_gen {
+            None
+        } else {
+            unsafe {
+                let arena = &mut *self.arena;
+                let idx = self.gen_idx.as_usize();
+                let strong = arena.strong[idx / MAX_ALLOCS]
+                    .as_mut_ptr()
+                    .add(idx % MAX_ALLOCS);
+                *strong += 1;
+            }
+            Some(Gr {
+                ptr: self.ptr,
+                gen_idx: self.gen_idx,
+                arena: self.arena,
+                phantom: PhantomData,
+            })
+        }
+    }
+}
+
+/// Thread-safe counterpart to `GrArena`.
+///
+/// Generation numbers live in `AtomicU64` cells so `SyncWeak::get` validates
+/// a handle with a lock-free `Ordering::Acquire` load instead of taking a
+/// lock on every access. Only the free list and slab growth -- the
+/// structural mutations `alloc` and returning a released slot need -- take the
+/// `Mutex`; that's the same split a `GCArena` built on `Arc`/`Mutex` would
+/// use. Strong counts are likewise `AtomicU64`, bumped with `fetch_add` on
+/// `clone` and raced down to zero with `fetch_sub` on `Drop`, following the
+/// same protocol `Arc`'s `Weak::upgrade` uses to avoid resurrecting a slot
+/// another thread just freed. Unlike `GrArena`, generation and index widths
+/// are not generic here: `u64` leaves the ABA window wide enough that the
+/// retirement added for `GrArena` is effectively unreachable in practice.
+struct SyncGrArenaInternal<T> {
+    gens: Vec<Box<[AtomicU64; MAX_ALLOCS]>>,
+    strong: Vec<Box<[AtomicU64; MAX_ALLOCS]>>,
+    slots: Vec<Box<[MaybeUninit<T>; MAX_ALLOCS]>>,
+    unused: Vec<usize>,
+}
+
+pub struct SyncGrArena<T> {
+    inner: Mutex<SyncGrArenaInternal<T>>,
+}
+
+impl<T> SyncGrArena<T> {
+    pub fn new() -> Self {
+        SyncGrArena {
+            inner: Mutex::new(SyncGrArenaInternal {
+                gens: Vec::new(),
+                strong: Vec::new(),
+                slots: Vec::new(),
+                unused: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn alloc<'a>(&'a self, v: T) -> SyncGr<'a, T> {
+        let mut arena = self.inner.lock().unwrap();
+        loop {
+            match arena.unused.pop() {
+                Some(idx) => {
+                    // Found an unused slot: it starts out with a single owner
+                    arena.strong[idx / MAX_ALLOCS][idx % MAX_ALLOCS].store(1, Ordering::Relaxed);
+                    let gen: *const AtomicU64 = &arena.gens[idx / MAX_ALLOCS][idx % MAX_ALLOCS];
+                    let strong: *const AtomicU64 = &arena.strong[idx / MAX_ALLOCS][idx % MAX_ALLOCS];
+                    let slot = &mut arena.slots[idx / MAX_ALLOCS][idx % MAX_ALLOCS];
+                    slot.write(v);
+                    return SyncGr {
+                        ptr: NonNull::new(slot.as_mut_ptr()).unwrap(),
+                        gen,
+                        strong,
+                        idx,
+                        arena: self,
+                        phantom: PhantomData,
+                    };
+                }
+                None => {
+                    // Add more slots if we ran out
+                    let base = arena.gens.len() * MAX_ALLOCS;
+                    arena
+                        .gens
+                        .push(Box::new(std::array::from_fn(|_| AtomicU64::new(1))));
+                    arena
+                        .strong
+                        .push(Box::new(std::array::from_fn(|_| AtomicU64::new(0))));
+                    arena
+                        .slots
+                        .push(Box::new(std::array::from_fn(|_| MaybeUninit::uninit())));
+                    for i in 0..MAX_ALLOCS {
+                        arena.unused.push(i + base);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Look up a value by a `SyncWeak` handle, without going through
+    /// `SyncWeak::get`. Mirrors `GrArena::get`.
+    pub fn get(&self, weak: &SyncWeak<'_, T>) -> Option<&T> {
+        if unsafe { &*weak.gen }.load(Ordering::Acquire) != weak.alloc_gen {
+            None
+        } else {
+            Some(unsafe { weak.ptr.as_ref() })
+        }
+    }
+}
+
+pub struct SyncGr<'a, T> {
+    // The contained data
+    ptr: NonNull<T>,
+    // Pointer to this slot's generation cell, captured once at alloc time so
+    // later access doesn't need to re-lock the arena or re-index its slabs.
+    gen: *const AtomicU64,
+    // Pointer to this slot's strong-count cell, same reasoning as `gen`.
+    strong: *const AtomicU64,
+    // The index into the generational numbers array
+    idx: usize,
+    // The owning arena, needed to push the slot back onto the free list
+    // once the last owner drops.
+    arena: &'a SyncGrArena<T>,
+    // Must not outlive the arena
+    phantom: PhantomData<&'a T>,
+}
+
+// Safety: the slot a `SyncGr` points into is only ever reachable through
+// `SyncGr`/`SyncWeak` handles guarded by atomic generation/strong-count
+// cells, so handing one to another thread is sound as long as `T` itself
+// is `Send`/`Sync`.
+unsafe impl<'a, T: Send + Sync> Send for SyncGr<'a, T> {}
+unsafe impl<'a, T: Send + Sync> Sync for SyncGr<'a, T> {}
+
+impl<'a, T> SyncGr<'a, T> {
+    pub fn weak(&self) -> SyncWeak<'a, T> {
+        SyncWeak {
+            ptr: self.ptr,
+            gen: self.gen,
+            alloc_gen: unsafe { &*self.gen }.load(Ordering::Acquire),
+            strong: self.strong,
+            idx: self.idx,
+            arena: self.arena,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Clone for SyncGr<'a, T> {
+    fn clone(&self) -> Self {
+        // Another owner of the same slot: bump the strong count instead of
+        // allocating anything.
+        unsafe { &*self.strong }.fetch_add(1, Ordering::Relaxed);
+        SyncGr {
+            ptr: self.ptr,
+            gen: self.gen,
+            strong: self.strong,
+            idx: self.idx,
+            arena: self.arena,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Runs the "last owner just left" protocol for a slot: drop the value in
+/// place, then either retire the slot for good or bump its generation and
+/// return it to the free list. Shared by `Drop for SyncGr` and
+/// `SyncWeak::upgrade`'s backout path -- both can end up being the decrement
+/// that observes the strong count hit zero, and both must finish releasing
+/// the slot when that happens, or its destructor never runs and it leaks
+/// forever instead of the bounded one-slot-per-wrap leak retirement expects.
+///
+/// # Safety
+/// `ptr` must point at a live, initialized `T` that no other `SyncGr`/
+/// `SyncWeak` still considers owned, i.e. the caller's `fetch_sub` must have
+/// just observed the strong count drop to zero.
+unsafe fn release_sync_slot<T>(arena: &SyncGrArena<T>, gen: *const AtomicU64, idx: usize, ptr: *mut T) {
+    // Same protocol as `Arc`: an `Acquire` fence here so our drop of the
+    // value happens-after every other owner's access.
+    std::sync::atomic::fence(Ordering::Acquire);
+    std::ptr::drop_in_place(ptr);
+    let gen = &*gen;
+    let next = gen.load(Ordering::Relaxed).wrapping_add(1);
+    if next == 0 {
+        // Bumping the generation would wrap it back to a value some
+        // earlier, possibly still-alive `SyncWeak` could hold. Retire the
+        // slot for good instead of risking that ABA collision, same as
+        // `GrArena`'s narrow-generation handling.
+        gen.store(0, Ordering::Release);
+    } else {
+        gen.store(next, Ordering::Release);
+        arena.inner.lock().unwrap().unused.push(idx);
+    }
+}
+
+impl<'a, T> Drop for SyncGr<'a, T> {
+    fn drop(&mut self) {
+        // `Release` on the decrement so the drop of the value happens-after
+        // every other owner's access.
+        if unsafe { &*self.strong }.fetch_sub(1, Ordering::Release) == 1 {
+            unsafe { release_sync_slot(self.arena, self.gen, self.idx, self.ptr.as_ptr()) };
+        }
+    }
+}
+
+pub struct SyncWeak<'a, T> {
+    ptr: NonNull<T>,
+    gen: *const AtomicU64,
+    // The generational number we expect
+    alloc_gen: u64,
+    strong: *const AtomicU64,
+    // The index into the generational numbers array
+    idx: usize,
+    // The owning arena, needed to re-acquire ownership via `upgrade`.
+    arena: &'a SyncGrArena<T>,
+    // Must not outlive the arena
+    phantom: PhantomData<&'a T>,
+}
+
+// Safety: see `SyncGr`.
+unsafe impl<'a, T: Send + Sync> Send for SyncWeak<'a, T> {}
+unsafe impl<'a, T: Send + Sync> Sync for SyncWeak<'a, T> {}
+
+impl<'a, T> SyncWeak<'a, T> {
+    pub fn get(&self) -> Option<&T> {
+        if unsafe { &*self.gen }.load(Ordering::Acquire) != self.alloc_gen {
+            None
+        } else {
+            Some(unsafe { self.ptr.as_ref() })
+        }
+    }
+
+    /// Try to promote this `SyncWeak` back into an owning `SyncGr`.
+    ///
+    /// Uses the same compare-exchange loop as `std::sync::Weak::upgrade`:
+    /// we must not bump the strong count off of zero, since a concurrent
+    /// `Drop` racing us there may already be about to release this slot for
+    /// reuse by a completely different allocation.
+    pub fn upgrade(&self) -> Option<SyncGr<'a, T>> {
+        let strong = unsafe { &*self.strong };
+        let mut cur = strong.load(Ordering::Relaxed);
+        loop {
+            if cur == 0 {
+                return None;
+            }
+            match strong.compare_exchange_weak(
+                cur,
+                cur + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(observed) => cur = observed,
+            }
+        }
+        if unsafe { &*self.gen }.load(Ordering::Acquire) != self.alloc_gen {
+            // The slot was released and reused for something else between
+            // our load and our strong-count bump: back out and fail. If our
+            // decrement is the one that brings the count to zero -- we raced
+            // the new owner's `Drop` down to zero -- we're now the last
+            // owner of whatever is currently in the slot, so we must finish
+            // releasing it ourselves instead of silently dropping the count
+            // and walking away.
+            if strong.fetch_sub(1, Ordering::Release) == 1 {
+                unsafe { release_sync_slot(self.arena, self.gen, self.idx, self.ptr.as_ptr()) };
+            }
+            return None;
+        }
+        Some(SyncGr {
+            ptr: self.ptr,
+            gen: self.gen,
+            strong: self.strong,
+            idx: self.idx,
+            arena: self.arena,
+            phantom: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::GrArena;
+    use crate::{release_sync_slot, GrArena, SyncGrArena};
 
     #[test]
     fn it_works() {
-        let arena = GrArena::new();
+        let arena: GrArena<String> = GrArena::new();
         let r1;
         let r2;
         {
@@ -152,7 +697,7 @@ mod tests {
     #[test]
     fn many() {
         // Test that we can allocate and use many things
-        let arena = GrArena::new();
+        let arena: GrArena<String> = GrArena::new();
         let mut allocs = Vec::new();
 
         for _ in 0..3 {
@@ -180,7 +725,7 @@ mod tests {
 
             // Store all weak refs, drop all owning refs, and test that none can be retrieved
             let mut weak_refs = Vec::new();
-            
+
             for or in allocs.iter() {
                 weak_refs.push(or.weak());
             }
@@ -192,4 +737,183 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn clone_shares_ownership() {
+        let arena: GrArena<String> = GrArena::new();
+        let a = arena.alloc(String::from("Hello World"));
+        let w = a.weak();
+        {
+            // A clone is a second owner of the same slot.
+            let b = a.clone();
+            assert_eq!(b.weak().get(), Some(&String::from("Hello World")));
+            // Dropping one of the two owners must not invalidate the slot.
+        }
+        assert_eq!(w.get(), Some(&String::from("Hello World")));
+        drop(a);
+        // Now that the last owner is gone, the weak ref is dead.
+        assert_eq!(w.get(), None);
+    }
+
+    #[test]
+    fn weak_upgrade() {
+        let arena: GrArena<String> = GrArena::new();
+        let a = arena.alloc(String::from("Hello World"));
+        let w = a.weak();
+
+        let upgraded = w.upgrade().expect("owner is still alive");
+        assert_eq!(upgraded.weak().get(), Some(&String::from("Hello World")));
+
+        drop(a);
+        // The upgraded `Gr` is still a live owner, so the slot survives.
+        assert_eq!(w.get(), Some(&String::from("Hello World")));
+
+        drop(upgraded);
+        // All owners are gone now, so the slot is freed and upgrade fails.
+        assert!(w.upgrade().is_none());
+    }
+
+    #[test]
+    fn iter_over_live_values() {
+        let mut arena: GrArena<String> = GrArena::new();
+        let a = arena.alloc(String::from("a"));
+        let b = arena.alloc(String::from("b"));
+
+        let mut seen: Vec<&String> = arena.iter().collect();
+        seen.sort();
+        assert_eq!(seen, vec![&String::from("a"), &String::from("b")]);
+
+        // `iter_mut` needs exclusive access to the arena, which (unlike
+        // `iter`) the borrow checker only grants once every `Gr` borrowing it
+        // has actually gone away -- forget them to hand conceptual ownership
+        // to the arena for the rest of this test.
+        std::mem::forget(a);
+        std::mem::forget(b);
+
+        for s in arena.iter_mut() {
+            s.push('!');
+        }
+        let mut seen: Vec<&String> = arena.iter().collect();
+        seen.sort();
+        assert_eq!(seen, vec![&String::from("a!"), &String::from("b!")]);
+    }
+
+    #[test]
+    fn get_by_weak_handle() {
+        let arena: GrArena<String> = GrArena::new();
+        let a = arena.alloc(String::from("Hello World"));
+        let w = a.weak();
+
+        assert_eq!(arena.get(&w), Some(&String::from("Hello World")));
+        drop(a);
+        // The slot is gone, so looking it up through the arena also fails.
+        assert_eq!(arena.get(&w), None);
+    }
+
+    #[test]
+    fn get_does_not_read_through_a_different_arena() {
+        let arena_a: GrArena<String> = GrArena::new();
+        let arena_b: GrArena<String> = GrArena::new();
+        let a = arena_a.alloc(String::from("from A"));
+        let _b = arena_b.alloc(String::from("from B"));
+        let w = a.weak();
+
+        // `get` resolves `w` through its own captured generation/value
+        // pointers rather than recomputing an index into whichever arena
+        // it's called on, so handing it to the wrong arena still yields
+        // `a`'s value instead of reading through `arena_b`'s unrelated slot
+        // at the same index.
+        assert_eq!(arena_b.get(&w), Some(&String::from("from A")));
+    }
+
+    #[test]
+    fn narrow_generation_and_index_types() {
+        // `u16` generations/indices shrink `Weak` (40 to 32 bytes on 64-bit
+        // targets) compared to the `u64`/`usize` default, at the cost of wrapping
+        // sooner -- see `GenInt`/`IdxInt`.
+        let arena: GrArena<String, u16, u16> = GrArena::new();
+        let a = arena.alloc(String::from("Hello World"));
+        let w = a.weak();
+        assert_eq!(w.get(), Some(&String::from("Hello World")));
+        drop(a);
+        assert_eq!(w.get(), None);
+    }
+
+    #[test]
+    fn slot_retires_after_generation_overflow() {
+        let arena: GrArena<u32, u16, u16> = GrArena::new();
+        // Cycle the same slot through its entire u16 generation range so it
+        // retires instead of wrapping back around.
+        for i in 0..u16::MAX as u32 {
+            drop(arena.alloc(i));
+        }
+        // Retirement only leaks the one slot -- the arena keeps working by
+        // handing out a different one.
+        let a = arena.alloc(999);
+        assert_eq!(a.weak().get(), Some(&999));
+    }
+
+    #[test]
+    fn sync_arena_across_threads() {
+        let arena: SyncGrArena<i32> = SyncGrArena::new();
+        let a = arena.alloc(42);
+        let w = a.weak();
+
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                assert_eq!(w.get(), Some(&42));
+                let upgraded = w.upgrade().expect("owner is still alive");
+                assert_eq!(upgraded.weak().get(), Some(&42));
+                assert_eq!(arena.get(&w), Some(&42));
+            });
+        });
+
+        drop(a);
+        assert_eq!(w.get(), None);
+    }
+
+    struct DropCounter<'a>(&'a std::sync::atomic::AtomicUsize);
+
+    impl<'a> Drop for DropCounter<'a> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn release_sync_slot_drops_value_and_recycles_slot() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let drops = AtomicUsize::new(0);
+        let arena: SyncGrArena<DropCounter> = SyncGrArena::new();
+        let c = arena.alloc(DropCounter(&drops));
+        let (gen, idx, ptr) = (c.gen, c.idx, c.ptr.as_ptr());
+        // Don't run `c`'s own `Drop` -- we're driving the release protocol
+        // by hand below, the same way `SyncWeak::upgrade`'s backout path
+        // does when *its* decrement is the one that observes the strong
+        // count hit zero (see the race described in `upgrade`'s backout
+        // comment).
+        std::mem::forget(c);
+
+        unsafe { release_sync_slot(&arena, gen, idx, ptr) };
+
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+        // The slot must come back for reuse, not leak.
+        let d = arena.alloc(DropCounter(&drops));
+        assert_eq!(d.idx, idx);
+    }
+
+    #[test]
+    #[should_panic(expected = "I::MAX")]
+    fn narrow_index_type_panics_instead_of_aliasing_slots() {
+        // A `u16` index can't name more than `u16::MAX` live slots; growing
+        // the arena past that must refuse to silently wrap indices into
+        // collisions with already-live slots instead of handing out an
+        // index some other live `Gr` already owns.
+        let arena: GrArena<u64, u64, u16> = GrArena::new();
+        let mut allocs = Vec::new();
+        for i in 0..=u16::MAX as u32 + 10 {
+            allocs.push(arena.alloc(i as u64));
+        }
+    }
 }